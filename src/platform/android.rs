@@ -1,7 +1,10 @@
 use std::borrow::Cow;
+use std::env;
 use std::io::{self, Write};
+use std::os::unix::io::AsRawFd;
 use std::process::{Command, Stdio};
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use crate::common::{Error, ImageData};
 
@@ -23,75 +26,692 @@ fn termux_set() -> Command {
     }
 }
 
+/// Runs `cmd`, collecting stdout as a `String`.
+///
+/// `label` is only used to build readable error messages.
+fn run_capturing_stdout(mut cmd: Command, label: &str) -> Result<String, Error> {
+    let output = cmd
+        .output()
+        .map_err(|e| Error::unknown(format!("Failed to execute '{}': {}", label, e)))?;
+
+    if !output.status.success() {
+        return Err(Error::unknown(format!(
+            "'{}' exited with non-zero status: {}",
+            label,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| Error::ConversionFailure)
+}
+
+/// Runs `cmd` with `contents` piped to its stdin.
+///
+/// `label` is only used to build readable error messages.
+fn run_with_stdin(mut cmd: Command, contents: &str, label: &str) -> Result<(), Error> {
+    let mut process = cmd
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::unknown(format!("Failed to execute '{}': {}", label, e)))?;
+
+    let write_result = process
+        .stdin
+        .take()
+        .expect("stdin was requested as piped")
+        .write_all(contents.as_bytes());
+
+    // Wait for the child even if the write failed, so a broken pipe doesn't leave a zombie
+    // process behind.
+    let status = process
+        .wait()
+        .map_err(|e| Error::unknown(format!("Failed to wait for '{}': {}", label, e)))?;
+
+    write_result
+        .map_err(|e| Error::unknown(format!("Failed to write to stdin of '{}': {}", label, e)))?;
+
+    if !status.success() {
+        return Err(Error::unknown(format!("'{}' exited with non-zero status.", label)));
+    }
+
+    Ok(())
+}
+
+/// Distinguishes the ordinary clipboard from the X11/Wayland primary selection.
+///
+/// The primary selection holds whatever text is currently highlighted and is pasted with a
+/// middle click; it has no equivalent on Termux, so providers that can't honor it fall back to
+/// the ordinary clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxClipboardKind {
+    Clipboard,
+    Primary,
+}
+
+/// A clipboard backend, usually driven by an external command-line tool.
+///
+/// `arboard`'s Unix support works by shelling out to whatever clipboard helper is available
+/// (Termux:API, `wl-clipboard`, `xclip`, `xsel`, `tmux`, or a caller-supplied command), or by
+/// writing OSC 52 escape sequences to the terminal; this trait is the seam that lets
+/// [`Clipboard`] dispatch to whichever one was selected.
+pub(crate) trait ClipboardProvider: Send + Sync {
+    /// Identifier used for provider selection and diagnostics, e.g. `"termux"`.
+    fn name(&self) -> &'static str;
+
+    fn get_contents(&self, selection: LinuxClipboardKind) -> Result<String, Error>;
+
+    fn set_contents(&self, contents: &str, selection: LinuxClipboardKind) -> Result<(), Error>;
+}
+
+struct TermuxProvider;
+
+impl TermuxProvider {
+    fn new() -> Result<Self, Error> {
+        // Check for `termux-clipboard-get`
+        match termux_get().spawn() {
+            Ok(mut child) => {
+                child.kill().map_err(|e| Error::unknown(format!("Failed to kill test process for 'termux-clipboard-get': {}", e)))?;
+            }
+            Err(e) => {
+                if e.kind() == io::ErrorKind::NotFound {
+                    return Err(Error::unknown(
+                        "'termux-clipboard-get' command not found. Please install Termux:API.",
+                    ));
+                } else {
+                    return Err(Error::unknown(format!(
+                        "Error while testing for 'termux-clipboard-get': {}",
+                        e
+                    )));
+                }
+            }
+        };
+
+        // Check for `termux-clipboard-set`
+        match termux_set().spawn() {
+            Ok(mut child) => {
+                child.kill().map_err(|e| Error::unknown(format!("Failed to kill test process for 'termux-clipboard-set': {}", e)))?;
+            }
+            Err(e) => {
+                if e.kind() == io::ErrorKind::NotFound {
+                    return Err(Error::unknown(
+                        "'termux-clipboard-set' command not found. Please install Termux:API.",
+                    ));
+                } else {
+                    return Err(Error::unknown(format!(
+                        "Error while testing for 'termux-clipboard-set': {}",
+                        e
+                    )));
+                }
+            }
+        };
+
+        Ok(TermuxProvider)
+    }
+}
+
+impl ClipboardProvider for TermuxProvider {
+    fn name(&self) -> &'static str {
+        "termux"
+    }
+
+    fn get_contents(&self, _selection: LinuxClipboardKind) -> Result<String, Error> {
+        // Termux has no notion of a primary selection, so it always serves the clipboard.
+        run_capturing_stdout(termux_get(), "termux-clipboard-get")
+    }
+
+    fn set_contents(&self, contents: &str, _selection: LinuxClipboardKind) -> Result<(), Error> {
+        run_with_stdin(termux_set(), contents, "termux-clipboard-set")
+    }
+}
+
+/// Checks whether `name` resolves to an executable file somewhere on `$PATH`, the same way a
+/// shell would look it up.
+fn command_exists(name: &str) -> bool {
+    let Some(path) = env::var_os("PATH") else {
+        return false;
+    };
+    env::split_paths(&path).any(|dir| is_executable_file(&dir.join(name)))
+}
+
+#[cfg(unix)]
+fn is_executable_file(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Checks whether `/dev/tty` can be opened for reading and writing, i.e. whether OSC 52 has
+/// any chance of working.
+fn has_tty() -> bool {
+    std::fs::OpenOptions::new().read(true).write(true).open("/dev/tty").is_ok()
+}
+
+/// Whether this looks like a remote session reached over SSH, rather than a local terminal
+/// (including a Termux terminal-app session, which always has a `/dev/tty` but is not what
+/// OSC 52 auto-detection is for).
+fn is_remote_ssh_session() -> bool {
+    env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some()
+}
+
+/// Probes the environment for a working command-line clipboard tool and builds its provider,
+/// in order of preference: Wayland, then X11's `xsel`, then `xclip`, then Termux:API, then the
+/// tmux paste buffer (if running inside tmux), then OSC 52 as a last resort for a remote SSH
+/// session with no clipboard daemon reachable.
+///
+/// Used when the caller doesn't pick a provider explicitly via [`ClipboardBuilder::provider`],
+/// so the same binary works unmodified on a bare Linux box, a Wayland session, inside tmux
+/// over SSH with no clipboard daemon, or on Termux. Termux is checked ahead of OSC 52 so a
+/// real Termux:API session (which always has a `/dev/tty`, just like any terminal) isn't
+/// mistaken for a plain SSH session with no better option. The provider actually picked is
+/// available afterwards via [`Clipboard::provider_name`].
+fn detect_provider() -> Result<Box<dyn ClipboardProvider>, Error> {
+    if command_exists("wl-copy") && command_exists("wl-paste") {
+        return Ok(Box::new(WaylandProvider));
+    }
+    if command_exists("xsel") {
+        return Ok(Box::new(XselProvider));
+    }
+    if command_exists("xclip") {
+        return Ok(Box::new(XclipProvider));
+    }
+    if command_exists("termux-clipboard-get") && command_exists("termux-clipboard-set") {
+        return Ok(Box::new(TermuxProvider::new()?));
+    }
+    if tmux_buffer_available() {
+        return Ok(Box::new(TmuxBufferProvider::new()?));
+    }
+    if is_remote_ssh_session() && has_tty() {
+        return Ok(Box::new(Osc52Provider));
+    }
+    Ok(Box::new(TermuxProvider::new()?))
+}
+
+struct WaylandProvider;
+
+impl ClipboardProvider for WaylandProvider {
+    fn name(&self) -> &'static str {
+        "wayland"
+    }
+
+    fn get_contents(&self, selection: LinuxClipboardKind) -> Result<String, Error> {
+        let mut cmd = Command::new("wl-paste");
+        cmd.arg("--no-newline");
+        if selection == LinuxClipboardKind::Primary {
+            cmd.arg("--primary");
+        }
+        run_capturing_stdout(cmd, "wl-paste")
+    }
+
+    fn set_contents(&self, contents: &str, selection: LinuxClipboardKind) -> Result<(), Error> {
+        let mut cmd = Command::new("wl-copy");
+        if selection == LinuxClipboardKind::Primary {
+            cmd.arg("--primary");
+        }
+        run_with_stdin(cmd, contents, "wl-copy")
+    }
+}
+
+struct XclipProvider;
+
+impl ClipboardProvider for XclipProvider {
+    fn name(&self) -> &'static str {
+        "x-clip"
+    }
+
+    fn get_contents(&self, selection: LinuxClipboardKind) -> Result<String, Error> {
+        let mut cmd = Command::new("xclip");
+        cmd.args(["-selection", xclip_selection(selection), "-out"]);
+        run_capturing_stdout(cmd, "xclip")
+    }
 
-pub struct Clipboard;
+    fn set_contents(&self, contents: &str, selection: LinuxClipboardKind) -> Result<(), Error> {
+        let mut cmd = Command::new("xclip");
+        cmd.args(["-selection", xclip_selection(selection)]);
+        run_with_stdin(cmd, contents, "xclip")
+    }
+}
+
+fn xclip_selection(selection: LinuxClipboardKind) -> &'static str {
+    match selection {
+        LinuxClipboardKind::Clipboard => "clipboard",
+        LinuxClipboardKind::Primary => "primary",
+    }
+}
+
+struct XselProvider;
+
+impl ClipboardProvider for XselProvider {
+    fn name(&self) -> &'static str {
+        "x-sel"
+    }
+
+    fn get_contents(&self, selection: LinuxClipboardKind) -> Result<String, Error> {
+        let mut cmd = Command::new("xsel");
+        cmd.args([xsel_selection(selection), "--output"]);
+        run_capturing_stdout(cmd, "xsel")
+    }
+
+    fn set_contents(&self, contents: &str, selection: LinuxClipboardKind) -> Result<(), Error> {
+        let mut cmd = Command::new("xsel");
+        cmd.args([xsel_selection(selection), "--input"]);
+        run_with_stdin(cmd, contents, "xsel")
+    }
+}
+
+fn xsel_selection(selection: LinuxClipboardKind) -> &'static str {
+    match selection {
+        LinuxClipboardKind::Clipboard => "--clipboard",
+        LinuxClipboardKind::Primary => "--primary",
+    }
+}
+
+/// Whether the tmux paste buffer is reachable: running inside tmux (`$TMUX` is set) with the
+/// `tmux` binary on `$PATH`. Shared by [`TmuxBufferProvider::new`] and [`detect_provider`] so
+/// the two checks can't drift apart.
+fn tmux_buffer_available() -> bool {
+    env::var_os("TMUX").is_some() && command_exists("tmux")
+}
+
+/// Stores and retrieves clipboard text via tmux's paste buffer (`tmux load-buffer`/
+/// `save-buffer`), for use inside a tmux session where the outer terminal's clipboard isn't
+/// reachable but the tmux buffer is. Complements [`Osc52Provider`].
+struct TmuxBufferProvider;
+
+impl TmuxBufferProvider {
+    fn new() -> Result<Self, Error> {
+        if !tmux_buffer_available() {
+            return Err(Error::unknown(
+                "the 'tmux' provider requires running inside a tmux session with the 'tmux' binary on $PATH",
+            ));
+        }
+        Ok(TmuxBufferProvider)
+    }
+}
+
+impl ClipboardProvider for TmuxBufferProvider {
+    fn name(&self) -> &'static str {
+        "tmux"
+    }
+
+    fn get_contents(&self, _selection: LinuxClipboardKind) -> Result<String, Error> {
+        let mut cmd = Command::new("tmux");
+        cmd.args(["save-buffer", "-"]);
+        run_capturing_stdout(cmd, "tmux save-buffer")
+    }
+
+    fn set_contents(&self, contents: &str, _selection: LinuxClipboardKind) -> Result<(), Error> {
+        let mut cmd = Command::new("tmux");
+        cmd.args(["load-buffer", "-"]);
+        run_with_stdin(cmd, contents, "tmux load-buffer")
+    }
+}
+
+/// The practical size limit for an OSC 52 payload, counted in base64-encoded bytes. Terminals
+/// commonly cap the length of an escape sequence they accept, so longer payloads are rejected.
+const OSC52_MAX_ENCODED_LEN: usize = 74_994;
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        out.push(BASE64_ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(BASE64_ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            BASE64_ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+fn base64_decode(data: &str) -> Result<Vec<u8>, Error> {
+    fn value(byte: u8) -> Option<u8> {
+        match byte {
+            b'A'..=b'Z' => Some(byte - b'A'),
+            b'a'..=b'z' => Some(byte - b'a' + 26),
+            b'0'..=b'9' => Some(byte - b'0' + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let bytes: Vec<u8> = data.trim_end_matches('=').bytes().filter_map(value).collect();
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for chunk in bytes.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(Error::unknown("malformed base64: a trailing group can't hold a full byte"));
+        }
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        out.push((b0 << 2) | (b1 >> 4));
+        if chunk.len() > 2 {
+            let b2 = chunk[2];
+            out.push((b1 << 4) | (b2 >> 2));
+            if chunk.len() > 3 {
+                out.push((b2 << 6) | chunk[3]);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Wraps an OSC 52 sequence body (e.g. `c;<base64>` or `c;?`) for the terminal multiplexer in
+/// use, if any.
+///
+/// Both tmux and GNU screen intercept escape sequences from the programs they host, so a raw
+/// OSC 52 sequence never reaches the outer terminal; it has to be wrapped in a passthrough
+/// (DCS) sequence instead.
+fn wrap_for_multiplexer(body: &str) -> String {
+    let sequence = format!("\x1b]52;{}\x07", body);
+
+    if env::var_os("TMUX").is_some() {
+        // tmux passthrough: ESC P tmux ; <sequence with embedded ESCs doubled> ESC \
+        let doubled: String = sequence
+            .chars()
+            .map(|c| if c == '\x1b' { "\x1b\x1b".to_string() } else { c.to_string() })
+            .collect();
+        format!("\x1bPtmux;{}\x1b\\", doubled)
+    } else if env::var_os("STY").is_some() || env::var("TERM").map(|t| t.starts_with("screen")).unwrap_or(false) {
+        // GNU screen limits a single DCS string to 768 bytes, so long sequences must be
+        // split across multiple passthrough chunks.
+        sequence
+            .as_bytes()
+            .chunks(768)
+            .map(|chunk| format!("\x1bP{}\x1b\\", String::from_utf8_lossy(chunk)))
+            .collect()
+    } else {
+        sequence
+    }
+}
+
+fn osc52_set(contents: &str) -> Result<(), Error> {
+    let encoded = base64_encode(contents.as_bytes());
+    if encoded.len() > OSC52_MAX_ENCODED_LEN {
+        return Err(Error::unknown(format!(
+            "clipboard contents are too large for OSC 52 ({} encoded bytes, limit is {})",
+            encoded.len(),
+            OSC52_MAX_ENCODED_LEN
+        )));
+    }
+
+    let sequence = wrap_for_multiplexer(&format!("c;{}", encoded));
+
+    let mut tty = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|e| Error::unknown(format!("Failed to open /dev/tty: {}", e)))?;
+    tty.write_all(sequence.as_bytes())
+        .map_err(|e| Error::unknown(format!("Failed to write OSC 52 sequence to /dev/tty: {}", e)))?;
+    tty.flush().map_err(|e| Error::unknown(format!("Failed to flush /dev/tty: {}", e)))
+}
+
+fn enable_raw_mode(fd: i32) -> Result<libc::termios, Error> {
+    unsafe {
+        let mut original: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut original) != 0 {
+            return Err(Error::unknown("Failed to read /dev/tty attributes"));
+        }
+        let mut raw = original;
+        libc::cfmakeraw(&mut raw);
+        if libc::tcsetattr(fd, libc::TCSANOW, &raw) != 0 {
+            return Err(Error::unknown("Failed to set /dev/tty to raw mode"));
+        }
+        Ok(original)
+    }
+}
+
+fn restore_termios(fd: i32, original: &libc::termios) {
+    unsafe {
+        libc::tcsetattr(fd, libc::TCSANOW, original);
+    }
+}
+
+/// Reads a `\x1b]52;c;<base64>` reply terminated by BEL or ST, polling `fd` so a terminal
+/// that never answers (no OSC 52 support) doesn't hang the caller forever.
+fn read_osc52_reply(fd: i32, timeout: Duration) -> Result<String, Error> {
+    let deadline = Instant::now() + timeout;
+    let mut buf = Vec::new();
+    let mut byte = [0u8; 1];
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(Error::unknown("Timed out waiting for an OSC 52 reply from the terminal"));
+        }
+
+        let mut pfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+        let timeout_ms = remaining.as_millis().min(i32::MAX as u128) as i32;
+        if unsafe { libc::poll(&mut pfd, 1, timeout_ms) } <= 0 {
+            return Err(Error::unknown("Timed out waiting for an OSC 52 reply from the terminal"));
+        }
+
+        let n = unsafe { libc::read(fd, byte.as_mut_ptr() as *mut libc::c_void, 1) };
+        if n <= 0 {
+            return Err(Error::unknown("Failed to read the OSC 52 reply from the terminal"));
+        }
+        buf.push(byte[0]);
+
+        if buf.ends_with(b"\x07") || buf.ends_with(b"\x1b\\") {
+            break;
+        }
+        if buf.len() > OSC52_MAX_ENCODED_LEN + 32 {
+            return Err(Error::unknown("OSC 52 reply from the terminal exceeded the expected size"));
+        }
+    }
+
+    let reply = String::from_utf8_lossy(&buf);
+    let body_start = reply.find(";c;").map(|i| i + 3).ok_or_else(|| Error::unknown("Malformed OSC 52 reply from the terminal"))?;
+    let body = reply[body_start..].trim_end_matches('\x07').trim_end_matches("\x1b\\");
+    String::from_utf8(base64_decode(body)?).map_err(|_| Error::ConversionFailure)
+}
+
+fn osc52_get() -> Result<String, Error> {
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|e| Error::unknown(format!("Failed to open /dev/tty: {}", e)))?;
+    let fd = tty.as_raw_fd();
+
+    let original = enable_raw_mode(fd)?;
+    let result = (|| {
+        let query = wrap_for_multiplexer("c;?");
+        tty.write_all(query.as_bytes())
+            .map_err(|e| Error::unknown(format!("Failed to write OSC 52 query to /dev/tty: {}", e)))?;
+        tty.flush().map_err(|e| Error::unknown(format!("Failed to flush /dev/tty: {}", e)))?;
+
+        read_osc52_reply(fd, Duration::from_millis(500))
+    })();
+    restore_termios(fd, &original);
+
+    result
+}
+
+struct Osc52Provider;
+
+impl ClipboardProvider for Osc52Provider {
+    fn name(&self) -> &'static str {
+        "osc52"
+    }
+
+    fn get_contents(&self, _selection: LinuxClipboardKind) -> Result<String, Error> {
+        // OSC 52's `c` target is the clipboard; there's no primary-selection equivalent.
+        osc52_get()
+    }
+
+    fn set_contents(&self, contents: &str, _selection: LinuxClipboardKind) -> Result<(), Error> {
+        osc52_set(contents)
+    }
+}
+
+/// A provider backed by a caller-supplied command for each direction.
+///
+/// `get_cmd`/`set_cmd` are full argument vectors (`argv[0]` is the executable). `set_contents`
+/// pipes the new clipboard text to `set_cmd`'s stdin.
+struct CustomProvider {
+    get_cmd: Vec<String>,
+    set_cmd: Vec<String>,
+}
+
+impl CustomProvider {
+    fn new(get_cmd: Vec<String>, set_cmd: Vec<String>) -> Self {
+        Self { get_cmd, set_cmd }
+    }
+}
+
+impl ClipboardProvider for CustomProvider {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn get_contents(&self, _selection: LinuxClipboardKind) -> Result<String, Error> {
+        // The caller's command already encodes whatever target it wants; there's no generic
+        // way to ask it for the primary selection instead.
+        let (program, args) = self
+            .get_cmd
+            .split_first()
+            .ok_or_else(|| Error::unknown("the custom provider's get command is empty"))?;
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        run_capturing_stdout(cmd, program)
+    }
+
+    fn set_contents(&self, contents: &str, _selection: LinuxClipboardKind) -> Result<(), Error> {
+        let (program, args) = self
+            .set_cmd
+            .split_first()
+            .ok_or_else(|| Error::unknown("the custom provider's set command is empty"))?;
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        run_with_stdin(cmd, contents, program)
+    }
+}
+
+/// Builds a [`Clipboard`], optionally selecting which [`ClipboardProvider`] backs it.
+///
+/// Leaving the provider unset probes `$PATH` and picks the first working tool (see
+/// [`detect_provider`]). Use [`Self::provider`] to select a built-in provider by name
+/// (`"termux"`, `"wayland"`, `"x-clip"`, `"x-sel"`, `"osc52"`, `"tmux"`), or
+/// [`Self::custom_provider`] to run your own get/set commands.
+pub struct ClipboardBuilder {
+    provider: Option<&'static str>,
+    custom: Option<(Vec<String>, Vec<String>)>,
+}
+
+impl ClipboardBuilder {
+    pub fn new() -> Self {
+        Self { provider: None, custom: None }
+    }
+
+    /// Selects a built-in provider by name: `"termux"`, `"wayland"`, `"x-clip"`, `"x-sel"`,
+    /// `"osc52"`, or `"tmux"`.
+    ///
+    /// For a fully custom command, use [`Self::custom_provider`] instead.
+    pub fn provider(mut self, name: &'static str) -> Self {
+        self.provider = Some(name);
+        self
+    }
+
+    /// Selects the `"custom"` provider: `get_cmd` is run to fetch the clipboard text, and
+    /// `contents` are piped to `set_cmd`'s stdin to store it. Both are full argument vectors.
+    pub fn custom_provider(mut self, get_cmd: Vec<String>, set_cmd: Vec<String>) -> Self {
+        self.provider = Some("custom");
+        self.custom = Some((get_cmd, set_cmd));
+        self
+    }
+
+    fn build_provider(self) -> Result<Box<dyn ClipboardProvider>, Error> {
+        let Some(name) = self.provider else {
+            return detect_provider();
+        };
+        match name {
+            "termux" => Ok(Box::new(TermuxProvider::new()?)),
+            "wayland" => Ok(Box::new(WaylandProvider)),
+            "x-clip" => Ok(Box::new(XclipProvider)),
+            "x-sel" => Ok(Box::new(XselProvider)),
+            "osc52" => Ok(Box::new(Osc52Provider)),
+            "tmux" => Ok(Box::new(TmuxBufferProvider::new()?)),
+            "custom" => {
+                let (get_cmd, set_cmd) = self.custom.ok_or_else(|| {
+                    Error::unknown("the 'custom' provider requires custom_provider() to supply get/set commands")
+                })?;
+                Ok(Box::new(CustomProvider::new(get_cmd, set_cmd)))
+            }
+            other => Err(Error::unknown(format!("unknown clipboard provider '{}'", other))),
+        }
+    }
+
+    pub fn build(self) -> Result<Clipboard, Error> {
+        Ok(Clipboard { provider: self.build_provider()? })
+    }
+}
+
+impl Default for ClipboardBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Clipboard {
+    provider: Box<dyn ClipboardProvider>,
+}
 
 impl Clipboard {
-	pub fn new() -> Result<Self, Error> {
-		// Check for `termux-clipboard-get`
-		match termux_get().spawn() {
-			Ok(mut child) => {
-				child.kill().map_err(|e| Error::unknown(format!("Failed to kill test process for 'termux-clipboard-get': {}", e)))?;
-			}
-			Err(e) => {
-				if e.kind() == io::ErrorKind::NotFound {
-					return Err(Error::unknown(
-						"'termux-clipboard-get' command not found. Please install Termux:API.",
-					));
-				} else {
-					return Err(Error::unknown(format!(
-						"Error while testing for 'termux-clipboard-get': {}",
-						e
-					)));
-				}
-			}
-		};
-
-		// Check for `termux-clipboard-set`
-		match termux_set().spawn() {
-			Ok(mut child) => {
-				child.kill().map_err(|e| Error::unknown(format!("Failed to kill test process for 'termux-clipboard-set': {}", e)))?;
-			}
-			Err(e) => {
-				if e.kind() == io::ErrorKind::NotFound {
-					return Err(Error::unknown(
-						"'termux-clipboard-set' command not found. Please install Termux:API.",
-					));
-				} else {
-					return Err(Error::unknown(format!(
-						"Error while testing for 'termux-clipboard-set': {}",
-						e
-					)));
-				}
-			}
-		};
-
-		Ok(Clipboard)
-	}
+    pub fn new() -> Result<Self, Error> {
+        ClipboardBuilder::new().build()
+    }
+
+    /// Starts building a [`Clipboard`] backed by a specific [`ClipboardProvider`], selected by
+    /// name via [`ClipboardBuilder::provider`] or [`ClipboardBuilder::custom_provider`].
+    pub fn builder() -> ClipboardBuilder {
+        ClipboardBuilder::new()
+    }
+
+    /// The name of the provider backing this clipboard, e.g. `"wayland"` or `"termux"`.
+    ///
+    /// Mainly useful for diagnostics when the provider was auto-detected rather than picked
+    /// explicitly via [`ClipboardBuilder::provider`].
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.name()
+    }
 }
 
 pub(crate) struct Get<'clipboard> {
-	_clipboard: &'clipboard Clipboard,
+    clipboard: &'clipboard Clipboard,
+    selection: LinuxClipboardKind,
 }
 
 impl<'clipboard> Get<'clipboard> {
 	pub(crate) fn new(clipboard: &'clipboard mut Clipboard) -> Self {
-		Self { _clipboard: clipboard }
+		Self { clipboard, selection: LinuxClipboardKind::Clipboard }
+	}
+
+	/// Targets the ordinary clipboard (the default).
+	pub(crate) fn clipboard(mut self) -> Self {
+		self.selection = LinuxClipboardKind::Clipboard;
+		self
+	}
+
+	/// Targets the X11/Wayland primary selection instead of the clipboard.
+	pub(crate) fn primary(mut self) -> Self {
+		self.selection = LinuxClipboardKind::Primary;
+		self
 	}
 
 	pub(crate) fn text(self) -> Result<String, Error> {
-		let output = termux_get().output().map_err(|e| {
-			Error::unknown(format!("Failed to execute 'termux-clipboard-get': {}", e))
-		})?;
-
-		if !output.status.success() {
-			return Err(Error::unknown(format!(
-				"'termux-clipboard-get' exited with non-zero status: {}",
-				String::from_utf8_lossy(&output.stderr)
-			)));
-		}
-
-		String::from_utf8(output.stdout).map_err(|_| Error::ConversionFailure)
+		self.clipboard.provider.get_contents(self.selection)
 	}
 
     pub(crate) fn html(self) -> Result<String, Error> {
@@ -109,37 +729,29 @@ impl<'clipboard> Get<'clipboard> {
 }
 
 pub(crate) struct Set<'clipboard> {
-	_clipboard: &'clipboard mut Clipboard,
+    clipboard: &'clipboard mut Clipboard,
+    selection: LinuxClipboardKind,
 }
 
 impl<'clipboard> Set<'clipboard> {
 	pub(crate) fn new(clipboard: &'clipboard mut Clipboard) -> Self {
-		Self { _clipboard: clipboard }
+		Self { clipboard, selection: LinuxClipboardKind::Clipboard }
+	}
+
+	/// Targets the ordinary clipboard (the default).
+	pub(crate) fn clipboard(mut self) -> Self {
+		self.selection = LinuxClipboardKind::Clipboard;
+		self
+	}
+
+	/// Targets the X11/Wayland primary selection instead of the clipboard.
+	pub(crate) fn primary(mut self) -> Self {
+		self.selection = LinuxClipboardKind::Primary;
+		self
 	}
 
 	pub(crate) fn text(self, text: Cow<'_, str>) -> Result<(), Error> {
-        let mut process = termux_set()
-			.stdin(Stdio::piped())
-			.spawn()
-			.map_err(|e| Error::unknown(format!("Failed to execute 'termux-clipboard-set': {}", e)))?;
-
-		if let Some(mut stdin) = process.stdin.take() {
-			stdin
-				.write_all(text.as_bytes())
-				.map_err(|e| Error::unknown(format!("Failed to write to stdin of 'termux-clipboard-set': {}", e)))?;
-		}
-
-		let status = process
-			.wait()
-			.map_err(|e| Error::unknown(format!("Failed to wait for 'termux-clipboard-set': {}", e)))?;
-
-		if !status.success() {
-			return Err(Error::unknown(
-				"'termux-clipboard-set' exited with non-zero status.",
-			));
-		}
-
-		Ok(())
+        self.clipboard.provider.set_contents(&text, self.selection)
 	}
 
     pub(crate) fn html(self, _html: Cow<'_, str>, _alt_text: Option<Cow<'_, str>>) -> Result<(), Error> {
@@ -207,4 +819,118 @@ mod tests {
         let content = fs::read_to_string("/tmp/arboard-test-clipboard").unwrap();
         assert_eq!(content, "");
     }
+
+    #[test]
+    fn test_base64_round_trip() {
+        for input in ["", "a", "ab", "abc", "hello, OSC 52!", &"x".repeat(100)] {
+            let encoded = base64_encode(input.as_bytes());
+            assert_eq!(base64_decode(&encoded).unwrap(), input.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_base64_known_vectors() {
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_decode("Zm9v").unwrap(), b"foo");
+    }
+
+    #[test]
+    fn test_base64_decode_rejects_truncated_trailing_group() {
+        // A single leftover base64 character can't encode any whole byte; accepting it would
+        // silently fabricate data instead of reporting the truncated input.
+        assert!(base64_decode("Z").is_err());
+    }
+
+    // `wrap_for_multiplexer` branches on the process-wide $TMUX/$STY env vars, and `cargo test`
+    // runs tests concurrently by default, so the tests below share this lock to keep them from
+    // racing each other's env var changes.
+    static MULTIPLEXER_ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn test_wrap_for_multiplexer_plain() {
+        let _guard = MULTIPLEXER_ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::remove_var("TMUX");
+        std::env::remove_var("STY");
+        std::env::set_var("TERM", "xterm-256color");
+        assert_eq!(wrap_for_multiplexer("c;aGk="), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn test_wrap_for_multiplexer_tmux_doubles_escapes() {
+        let _guard = MULTIPLEXER_ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var("TMUX", "/tmp/tmux-1000/default,1234,0");
+        let wrapped = wrap_for_multiplexer("c;aGk=");
+        assert!(wrapped.starts_with("\x1bPtmux;"));
+        assert!(wrapped.ends_with("\x1b\\"));
+        // The inner OSC 52 sequence's ESC is doubled so tmux's passthrough parser doesn't
+        // swallow it as its own terminator.
+        assert!(wrapped.contains("\x1b\x1b]52;c;aGk=\x07"));
+        std::env::remove_var("TMUX");
+    }
+
+    #[test]
+    fn test_wrap_for_multiplexer_screen_chunks_long_sequences() {
+        let _guard = MULTIPLEXER_ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::remove_var("TMUX");
+        std::env::set_var("STY", "12345.pts-0.host");
+        let body = format!("c;{}", "A".repeat(1000));
+        let wrapped = wrap_for_multiplexer(&body);
+        // GNU screen caps a single DCS string at 768 bytes, so a long payload must be split
+        // into more than one passthrough chunk.
+        assert!(wrapped.matches("\x1bP").count() > 1);
+        assert!(wrapped.contains("AAAA"));
+        std::env::remove_var("STY");
+    }
+
+    #[test]
+    fn test_custom_provider_round_trip() {
+        // Use a path unique to this test run rather than the `/tmp/arboard-test-clipboard`
+        // fixture shared by the mock Termux scripts, since `cargo test` runs tests
+        // concurrently and that file is written by other tests too.
+        let path = format!("/tmp/arboard-test-custom-clipboard-{}", std::process::id());
+        let mut clipboard = ClipboardBuilder::new()
+            .custom_provider(
+                vec!["cat".to_string(), path.clone()],
+                vec!["tee".to_string(), path.clone()],
+            )
+            .build()
+            .unwrap();
+        let text = "hello from the custom provider";
+        Set::new(&mut clipboard).text(Cow::from(text)).unwrap();
+        assert_eq!(Get::new(&mut clipboard).text().unwrap(), text);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_xclip_selection_args() {
+        assert_eq!(xclip_selection(LinuxClipboardKind::Clipboard), "clipboard");
+        assert_eq!(xclip_selection(LinuxClipboardKind::Primary), "primary");
+    }
+
+    #[test]
+    fn test_xsel_selection_args() {
+        assert_eq!(xsel_selection(LinuxClipboardKind::Clipboard), "--clipboard");
+        assert_eq!(xsel_selection(LinuxClipboardKind::Primary), "--primary");
+    }
+
+    #[test]
+    fn test_get_set_default_to_clipboard_and_primary_switches() {
+        let mut clipboard = Clipboard::new().unwrap();
+
+        let get = Get::new(&mut clipboard);
+        assert_eq!(get.selection, LinuxClipboardKind::Clipboard);
+        let get = get.primary();
+        assert_eq!(get.selection, LinuxClipboardKind::Primary);
+        let get = get.clipboard();
+        assert_eq!(get.selection, LinuxClipboardKind::Clipboard);
+
+        let set = Set::new(&mut clipboard);
+        assert_eq!(set.selection, LinuxClipboardKind::Clipboard);
+        let set = set.primary();
+        assert_eq!(set.selection, LinuxClipboardKind::Primary);
+        let set = set.clipboard();
+        assert_eq!(set.selection, LinuxClipboardKind::Clipboard);
+    }
 }